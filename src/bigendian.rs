@@ -1,4 +1,5 @@
 use std::cmp::min;
+use std::convert::TryInto;
 use std::mem::size_of;
 
 // manipulate bitstrings in form of slices of u*
@@ -198,15 +199,85 @@ macro_rules! impl_big_endian_for {
 	)
 }
 
+// every impl below operates on slices of the given width; callers are
+// responsible for storing the words already in big-endian *value* order
+// (most-significant word first) so that `get`/`mask`/`leading_zeros`
+// keep treating "bit 0" as the highest bit of element 0.
 impl_big_endian_for!{u8}
-// impl_big_endian_for!{u16}
-// impl_big_endian_for!{u32}
-// impl_big_endian_for!{u64}
+impl_big_endian_for!{u16}
+impl_big_endian_for!{u32}
+impl_big_endian_for!{u64}
+impl_big_endian_for!{u128}
+
+macro_rules! words_be {
+	($t:ty, $bytes:expr) => {
+		$bytes
+			.chunks_exact(size_of::<$t>())
+			.map(|chunk| <$t>::from_be_bytes(chunk.try_into().unwrap()))
+			.collect::<Vec<$t>>()
+	};
+}
+
+// pick the widest word size that evenly divides `$len`, bind it to the
+// type alias `$width`, and evaluate `$body` against it. This is what lets
+// a 16-byte `Ipv6Addr` be walked as a single `u128` (or two `u64`s)
+// instead of 16 individual `u8`s, cutting the per-bit loop count by
+// 8-16x; shared by every `*_widest` helper below so the dispatch itself
+// isn't duplicated per operation.
+macro_rules! with_widest_word {
+	($len:expr, $width:ident, $body:block) => {
+		if $len % size_of::<u128>() == 0 {
+			type $width = u128;
+			$body
+		} else if $len % size_of::<u64>() == 0 {
+			type $width = u64;
+			$body
+		} else if $len % size_of::<u32>() == 0 {
+			type $width = u32;
+			$body
+		} else if $len % size_of::<u16>() == 0 {
+			type $width = u16;
+			$body
+		} else {
+			type $width = u8;
+			$body
+		}
+	};
+}
+
+/// Compare the shared prefix of two equal-length big-endian byte slices,
+/// reinterpreting them as the widest word size that evenly divides their
+/// length.
+pub(crate) fn shared_prefix_len_widest(a: &[u8], b: &[u8], max_len: usize) -> usize {
+	debug_assert_eq!(a.len(), b.len());
+	with_widest_word!(a.len(), W, { W::shared_prefix_len(&words_be!(W, a), &words_be!(W, b), max_len) })
+}
+
+/// Widest-word equivalent of `BigEndianManipulation::contains`: whether
+/// `other` agrees with `slice` on the first `prefix` bits.
+pub(crate) fn contains_widest(slice: &[u8], prefix: usize, other: &[u8]) -> bool {
+	debug_assert_eq!(slice.len(), other.len());
+	with_widest_word!(slice.len(), W, { W::contains(&words_be!(W, slice), prefix, &words_be!(W, other)) })
+}
+
+/// Widest-word equivalent of `BigEndianManipulation::inc`: increment
+/// `bytes` from the right, leaving the first `prefix` bits untouched;
+/// returns `true` on overflow.
+pub(crate) fn inc_widest(bytes: &mut [u8], prefix: usize) -> bool {
+	with_widest_word!(bytes.len(), W, {
+		let mut words = words_be!(W, bytes);
+		let overflow = W::inc(&mut words, prefix);
+		for (chunk, word) in bytes.chunks_exact_mut(size_of::<W>()).zip(words.iter()) {
+			chunk.copy_from_slice(&word.to_be_bytes());
+		}
+		overflow
+	})
+}
 
 
 #[cfg(test)]
 mod tests {
-	use super::BigEndianManipulation;
+	use super::{shared_prefix_len_widest, BigEndianManipulation};
 
 	#[test]
 	fn shared_prefix() {
@@ -231,4 +302,91 @@ mod tests {
 		assert_eq!(15, u8::shared_prefix_len(&[0b0010_1000, 0b1100_0000], &[0b0010_1000, 0b1100_0001], 15));
 		assert_eq!(15, u8::shared_prefix_len(&[0b0010_1000, 0b1100_0000], &[0b0010_1000, 0b1100_0001], 16));
 	}
+
+	#[test]
+	fn shared_prefix_u32() {
+		assert_eq!(0, u32::shared_prefix_len(&[0x0000_0000, 0x0000_0000], &[0x0000_0000, 0x0000_0000], 0));
+		assert_eq!(0, u32::shared_prefix_len(&[0x0000_0000, 0x0000_0000], &[0x8000_0000, 0x0000_0000], 32));
+		assert_eq!(1, u32::shared_prefix_len(&[0x0000_0000, 0x0000_0000], &[0x0000_0000, 0x0000_0000], 1));
+		assert_eq!(31, u32::shared_prefix_len(&[0xffff_fffe, 0x0000_0000], &[0xffff_ffff, 0x0000_0000], 31));
+		assert_eq!(31, u32::shared_prefix_len(&[0xffff_fffe, 0x0000_0000], &[0xffff_ffff, 0x0000_0000], 32));
+
+		// identical first word, difference in second word
+		assert_eq!(32, u32::shared_prefix_len(&[0x1234_5678, 0x0000_0000], &[0x1234_5678, 0x0000_0000], 32));
+		assert_eq!(32, u32::shared_prefix_len(&[0x1234_5678, 0x0000_0000], &[0x1234_5678, 0x8000_0000], 64));
+		assert_eq!(33, u32::shared_prefix_len(&[0x1234_5678, 0x0000_0000], &[0x1234_5678, 0x0000_0000], 33));
+		assert_eq!(33, u32::shared_prefix_len(&[0x1234_5678, 0x0000_0000], &[0x1234_5678, 0x4000_0000], 64));
+		assert_eq!(63, u32::shared_prefix_len(&[0x1234_5678, 0xffff_fffe], &[0x1234_5678, 0xffff_ffff], 63));
+		assert_eq!(63, u32::shared_prefix_len(&[0x1234_5678, 0xffff_fffe], &[0x1234_5678, 0xffff_ffff], 64));
+	}
+
+	#[test]
+	fn shared_prefix_u64() {
+		assert_eq!(0, u64::shared_prefix_len(&[0x0000_0000_0000_0000, 0x0000_0000_0000_0000], &[0x0000_0000_0000_0000, 0x0000_0000_0000_0000], 0));
+		assert_eq!(0, u64::shared_prefix_len(&[0x0000_0000_0000_0000, 0x0000_0000_0000_0000], &[0x8000_0000_0000_0000, 0x0000_0000_0000_0000], 64));
+		assert_eq!(1, u64::shared_prefix_len(&[0x0000_0000_0000_0000, 0x0000_0000_0000_0000], &[0x0000_0000_0000_0000, 0x0000_0000_0000_0000], 1));
+
+		// identical first word, difference in second word
+		assert_eq!(64, u64::shared_prefix_len(&[0x0123_4567_89ab_cdef, 0x0000_0000_0000_0000], &[0x0123_4567_89ab_cdef, 0x0000_0000_0000_0000], 64));
+		assert_eq!(64, u64::shared_prefix_len(&[0x0123_4567_89ab_cdef, 0x0000_0000_0000_0000], &[0x0123_4567_89ab_cdef, 0x8000_0000_0000_0000], 128));
+		assert_eq!(65, u64::shared_prefix_len(&[0x0123_4567_89ab_cdef, 0x0000_0000_0000_0000], &[0x0123_4567_89ab_cdef, 0x0000_0000_0000_0000], 65));
+		assert_eq!(65, u64::shared_prefix_len(&[0x0123_4567_89ab_cdef, 0x0000_0000_0000_0000], &[0x0123_4567_89ab_cdef, 0x4000_0000_0000_0000], 128));
+		assert_eq!(127, u64::shared_prefix_len(&[0x0123_4567_89ab_cdef, 0xffff_ffff_ffff_fffe], &[0x0123_4567_89ab_cdef, 0xffff_ffff_ffff_ffff], 127));
+		assert_eq!(127, u64::shared_prefix_len(&[0x0123_4567_89ab_cdef, 0xffff_ffff_ffff_fffe], &[0x0123_4567_89ab_cdef, 0xffff_ffff_ffff_ffff], 128));
+	}
+
+	#[test]
+	fn shared_prefix_len_widest_picks_widest_word() {
+		// 16 bytes (an Ipv6Addr) divides evenly by u128, so this is a
+		// single-word comparison rather than 16 byte-wise ones
+		let a = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+		let b = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+		assert_eq!(127, shared_prefix_len_widest(&a, &b, 127));
+		assert_eq!(127, shared_prefix_len_widest(&a, &b, 128));
+
+		// 4 bytes (an Ipv4Addr) divides evenly by u32
+		assert_eq!(31, shared_prefix_len_widest(&[10, 0, 0, 0], &[10, 0, 0, 1], 32));
+
+		// odd lengths fall back to the byte-wise implementation
+		assert_eq!(15, shared_prefix_len_widest(&[0b0010_1000, 0b1100_0000, 0], &[0b0010_1000, 0b1100_0001, 0], 15));
+	}
+
+	#[test]
+	fn contains_widest_picks_widest_word() {
+		// 16 bytes (an Ipv6Addr): single u128 comparison
+		let network = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+		let inside = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+		let outside = [0x20, 0x01, 0x0d, 0xb9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+		assert!(super::contains_widest(&network, 32, &inside));
+		assert!(!super::contains_widest(&network, 32, &outside));
+
+		// 4 bytes (an Ipv4Addr): single u32 comparison
+		assert!(super::contains_widest(&[10, 0, 0, 0], 8, &[10, 0, 0, 1]));
+		assert!(!super::contains_widest(&[10, 0, 0, 0], 8, &[11, 0, 0, 1]));
+	}
+
+	#[test]
+	fn inc_widest_picks_widest_word() {
+		// 4 bytes (an Ipv4Addr): single u32 increment, prefix left untouched
+		let mut bytes = [10, 0, 0, 5];
+		assert!(!super::inc_widest(&mut bytes, 24));
+		assert_eq!(bytes, [10, 0, 0, 6]);
+
+		// host part wraps: the carry into the network prefix is undone and
+		// reported as overflow
+		let mut bytes = [10, 0, 0, 255];
+		assert!(super::inc_widest(&mut bytes, 24));
+		assert_eq!(bytes, [10, 0, 0, 0]);
+
+		// overflowing the whole word reports true
+		let mut bytes = [0xff, 0xff, 0xff, 0xff];
+		assert!(super::inc_widest(&mut bytes, 0));
+		assert_eq!(bytes, [0, 0, 0, 0]);
+
+		// 16 bytes (an Ipv6Addr): single u128 increment, host wraps
+		let mut bytes = [0u8; 16];
+		bytes[15] = 0xff;
+		assert!(super::inc_widest(&mut bytes, 120));
+		assert_eq!(bytes, [0u8; 16]);
+	}
 }
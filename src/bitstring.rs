@@ -0,0 +1,188 @@
+//! Optional integration with the `bitstring` crate, gated behind the
+//! `bitstring` cargo feature so no-std/minimal users don't pull in the
+//! extra dependency. Implementing `BitString`/`FixedBitString` for our
+//! own CIDR and address types lets downstream users drop them straight
+//! into a PATRICIA / radix tree keyed on prefix bits, instead of
+//! reimplementing bit access on top of `octets()`.
+#![cfg(feature = "bitstring")]
+
+use std::cmp::min;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use bitstring::{BitString, FixedBitString};
+
+use crate::bigendian::{shared_prefix_len_widest, BigEndianManipulation};
+use crate::cidr::{Ipv4Cidr, Ipv6Cidr};
+use crate::traits::Cidr;
+
+macro_rules! impl_bitstring_for_addr {
+	($addr:ty, $nbytes:expr, $bits:expr) => {
+		impl BitString for $addr {
+			fn clip(&mut self, len: usize) {
+				let mut bytes = self.octets();
+				u8::zeroesfrom(&mut bytes, len);
+				*self = <$addr>::from(bytes);
+			}
+
+			fn len(&self) -> usize {
+				$bits
+			}
+
+			fn get(&self, ndx: usize) -> bool {
+				u8::get(&self.octets(), ndx)
+			}
+
+			fn flip(&mut self, ndx: usize) {
+				let mut bytes = self.octets();
+				u8::flip(&mut bytes, ndx);
+				*self = <$addr>::from(bytes);
+			}
+
+			fn append_bit(&mut self, _bit: bool) {
+				panic!(concat!(stringify!($addr), " is a fixed ", stringify!($bits), "-bit string; it cannot grow past its width"))
+			}
+
+			fn null() -> Self {
+				<$addr>::from([0; $nbytes])
+			}
+
+			fn shared_prefix_len(&self, other: &Self) -> usize {
+				shared_prefix_len_widest(&self.octets(), &other.octets(), $bits)
+			}
+		}
+
+		impl FixedBitString for $addr {
+			fn with_capacity(_capacity: usize) -> Self {
+				Self::null()
+			}
+
+			fn all_zeros(_len: usize) -> Self {
+				<$addr>::from([0; $nbytes])
+			}
+
+			fn all_ones(_len: usize) -> Self {
+				<$addr>::from([0xff; $nbytes])
+			}
+		}
+	};
+}
+
+impl_bitstring_for_addr! { Ipv4Addr, 4, 32 }
+impl_bitstring_for_addr! { Ipv6Addr, 16, 128 }
+
+macro_rules! impl_bitstring_for_cidr {
+	($cidr:ty, $addr:ty, $nbytes:expr) => {
+		impl BitString for $cidr {
+			fn clip(&mut self, len: usize) {
+				let len = min(len, self.network_length() as usize) as u8;
+				let mut bytes = self.first_address().octets();
+				u8::zeroesfrom(&mut bytes, len as usize);
+				*self = <$cidr>::new(<$addr>::from(bytes), len).expect("clipping a network can't make it invalid");
+			}
+
+			fn len(&self) -> usize {
+				self.network_length() as usize
+			}
+
+			fn get(&self, ndx: usize) -> bool {
+				u8::get(&self.first_address().octets(), ndx)
+			}
+
+			fn flip(&mut self, ndx: usize) {
+				let mut bytes = self.first_address().octets();
+				u8::flip(&mut bytes, ndx);
+				*self = <$cidr>::new(<$addr>::from(bytes), self.network_length()).expect("flipping a bit within the network length can't make it invalid");
+			}
+
+			fn append_bit(&mut self, bit: bool) {
+				let len = self.network_length();
+				let mut bytes = self.first_address().octets();
+				u8::set(&mut bytes, len as usize, bit);
+				*self = <$cidr>::new(<$addr>::from(bytes), len + 1).expect("appending a bit can't make the network invalid");
+			}
+
+			fn null() -> Self {
+				<$cidr>::new(<$addr>::from([0; $nbytes]), 0).expect("the default route is always valid")
+			}
+
+			fn shared_prefix_len(&self, other: &Self) -> usize {
+				let max_len = min(self.len(), other.len());
+				shared_prefix_len_widest(&self.first_address().octets(), &other.first_address().octets(), max_len)
+			}
+		}
+
+		impl FixedBitString for $cidr {
+			fn with_capacity(_capacity: usize) -> Self {
+				Self::null()
+			}
+
+			fn all_zeros(len: usize) -> Self {
+				<$cidr>::new(<$addr>::from([0; $nbytes]), len as u8).expect("all-zeros network is always valid")
+			}
+
+			fn all_ones(len: usize) -> Self {
+				let mut bytes = [0xff; $nbytes];
+				u8::zeroesfrom(&mut bytes, len);
+				<$cidr>::new(<$addr>::from(bytes), len as u8).expect("all-ones network is always valid")
+			}
+		}
+	};
+}
+
+impl_bitstring_for_cidr! { Ipv4Cidr, Ipv4Addr, 4 }
+impl_bitstring_for_cidr! { Ipv6Cidr, Ipv6Addr, 16 }
+
+#[cfg(test)]
+mod tests {
+	use bitstring::{BitString, FixedBitString};
+	use std::net::Ipv4Addr;
+
+	use crate::Ipv4Cidr;
+
+	#[test]
+	fn addr_bit_ops() {
+		let addr = Ipv4Addr::new(255, 0, 0, 0);
+		assert_eq!(BitString::len(&addr), 32);
+		assert!(BitString::get(&addr, 0));
+		assert!(!BitString::get(&addr, 8));
+
+		let mut flipped = addr;
+		flipped.flip(8);
+		assert!(BitString::get(&flipped, 8));
+	}
+
+	#[test]
+	fn addr_shared_prefix_len() {
+		let a = Ipv4Addr::new(255, 255, 0, 0);
+		let b = Ipv4Addr::new(255, 254, 0, 0);
+		assert_eq!(a.shared_prefix_len(&b), 15);
+	}
+
+	#[test]
+	fn addr_null_and_all_ones() {
+		assert_eq!(Ipv4Addr::null(), Ipv4Addr::new(0, 0, 0, 0));
+		assert_eq!(<Ipv4Addr as FixedBitString>::all_ones(32), Ipv4Addr::new(255, 255, 255, 255));
+		assert_eq!(<Ipv4Addr as FixedBitString>::all_zeros(32), Ipv4Addr::new(0, 0, 0, 0));
+	}
+
+	#[test]
+	fn cidr_bit_ops() {
+		let cidr: Ipv4Cidr = "10.0.0.0/24".parse().unwrap();
+		assert_eq!(BitString::len(&cidr), 24);
+
+		let mut clipped = cidr;
+		clipped.clip(16);
+		assert_eq!(BitString::len(&clipped), 16);
+
+		let mut appended = clipped;
+		appended.append_bit(true);
+		assert_eq!(BitString::len(&appended), 17);
+	}
+
+	#[test]
+	fn cidr_shared_prefix_len() {
+		let a: Ipv4Cidr = "10.0.0.0/24".parse().unwrap();
+		let b: Ipv4Cidr = "10.0.1.0/24".parse().unwrap();
+		assert_eq!(a.shared_prefix_len(&b), 23);
+	}
+}
@@ -0,0 +1,257 @@
+// Core primitives for route table minimization and ACL compaction:
+// merging a set of networks into its smallest equivalent representation,
+// and splitting a network into the minimal set of networks covering
+// everything except some excluded sub-network.
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::bigendian::{shared_prefix_len_widest, BigEndianManipulation};
+
+use super::super::traits::*;
+use super::util::{is_subnet_of, Octets};
+use super::{IpCidr, Ipv4Cidr, Ipv6Cidr};
+
+fn cmp_cidr(a: &IpCidr, b: &IpCidr) -> Ordering {
+	match (a, b) {
+		(IpCidr::V4(_), IpCidr::V6(_)) => Ordering::Less,
+		(IpCidr::V6(_), IpCidr::V4(_)) => Ordering::Greater,
+		_ => a.first_address().cmp(&b.first_address()).then(a.network_length().cmp(&b.network_length())),
+	}
+}
+
+// if `a` and `b` are the two halves of a single `len - 1` parent (same
+// length, identical first `len - 1` bits, differing only in bit `len -
+// 1`), return that parent; `a` is assumed to be the numerically smaller
+// (i.e. the "zero" bit) half, as guaranteed by sorting before this is
+// called.
+fn sibling_parent_v4(a: &Ipv4Cidr, b: &Ipv4Cidr) -> Option<Ipv4Cidr> {
+	let len = a.network_length();
+	if len == 0 || len != b.network_length() {
+		return None;
+	}
+	let a_bytes = a.first_address().octets_vec();
+	let b_bytes = b.first_address().octets_vec();
+	if shared_prefix_len_widest(&a_bytes, &b_bytes, (len - 1) as usize) != (len - 1) as usize {
+		return None;
+	}
+	Ipv4Cidr::new(a.first_address(), len - 1).ok()
+}
+
+fn sibling_parent_v6(a: &Ipv6Cidr, b: &Ipv6Cidr) -> Option<Ipv6Cidr> {
+	let len = a.network_length();
+	if len == 0 || len != b.network_length() {
+		return None;
+	}
+	let a_bytes = a.first_address().octets_vec();
+	let b_bytes = b.first_address().octets_vec();
+	if shared_prefix_len_widest(&a_bytes, &b_bytes, (len - 1) as usize) != (len - 1) as usize {
+		return None;
+	}
+	Ipv6Cidr::new(a.first_address(), len - 1).ok()
+}
+
+fn sibling_parent(a: &IpCidr, b: &IpCidr) -> Option<IpCidr> {
+	match (a, b) {
+		(IpCidr::V4(a), IpCidr::V4(b)) => sibling_parent_v4(a, b).map(IpCidr::V4),
+		(IpCidr::V6(a), IpCidr::V6(b)) => sibling_parent_v6(a, b).map(IpCidr::V6),
+		_ => None,
+	}
+}
+
+/// Merge overlapping and adjacent sibling networks into the smallest
+/// equivalent set of CIDR blocks.
+///
+/// Networks already contained in another network of the set are
+/// dropped, then sibling pairs (two `/n` networks sharing a `/(n-1)`
+/// parent) are repeatedly coalesced until no more merges are possible.
+pub fn aggregate(cidrs: impl IntoIterator<Item = IpCidr>) -> Vec<IpCidr> {
+	let mut list: Vec<IpCidr> = cidrs.into_iter().collect();
+	list.sort_by(cmp_cidr);
+
+	let mut merged: Vec<IpCidr> = Vec::with_capacity(list.len());
+	for cidr in list {
+		if let Some(last) = merged.last() {
+			if is_subnet_of(&cidr, last) {
+				continue;
+			}
+		}
+		merged.push(cidr);
+	}
+
+	loop {
+		let mut next: Vec<IpCidr> = Vec::with_capacity(merged.len());
+		let mut changed = false;
+		let mut i = 0;
+		while i < merged.len() {
+			if i + 1 < merged.len() {
+				if let Some(parent) = sibling_parent(&merged[i], &merged[i + 1]) {
+					next.push(parent);
+					i += 2;
+					changed = true;
+					continue;
+				}
+			}
+			next.push(merged[i].clone());
+			i += 1;
+		}
+		merged = next;
+		if !changed {
+			break;
+		}
+		merged.sort_by(cmp_cidr);
+	}
+
+	merged
+}
+
+// descend from `network` towards `other`, splitting into halves and
+// keeping the half that doesn't contain `other` at each level.
+fn exclude_v4(network: &Ipv4Cidr, other: &Ipv4Cidr) -> Vec<Ipv4Cidr> {
+	if is_subnet_of(network, other) {
+		// self is entirely covered by other, so self \ other is empty
+		return Vec::new();
+	}
+	if !is_subnet_of(other, network) {
+		return vec![network.clone()];
+	}
+
+	let mut result = Vec::new();
+	let mut current = network.clone();
+	while current.network_length() < other.network_length() {
+		let next_len = current.network_length() + 1;
+		let zero_addr = current.first_address();
+		let mut one_bytes = zero_addr.octets_vec();
+		u8::flip(&mut one_bytes, (next_len - 1) as usize);
+		let one_addr = Ipv4Addr::from(<[u8; 4]>::try_from(one_bytes.as_slice()).unwrap());
+
+		// both halves are already correctly masked: `zero_addr` has bit
+		// `next_len - 1` unset (it was a host bit of `current`), and
+		// `one_addr` only has that single extra bit set
+		let zero_cidr = Ipv4Cidr::new(zero_addr, next_len).expect("split half is a valid network");
+		let one_cidr = Ipv4Cidr::new(one_addr, next_len).expect("split half is a valid network");
+
+		if zero_cidr.contains(&other.first_address()) {
+			result.push(one_cidr);
+			current = zero_cidr;
+		} else {
+			result.push(zero_cidr);
+			current = one_cidr;
+		}
+	}
+	result
+}
+
+fn exclude_v6(network: &Ipv6Cidr, other: &Ipv6Cidr) -> Vec<Ipv6Cidr> {
+	if is_subnet_of(network, other) {
+		// self is entirely covered by other, so self \ other is empty
+		return Vec::new();
+	}
+	if !is_subnet_of(other, network) {
+		return vec![network.clone()];
+	}
+
+	let mut result = Vec::new();
+	let mut current = network.clone();
+	while current.network_length() < other.network_length() {
+		let next_len = current.network_length() + 1;
+		let zero_addr = current.first_address();
+		let mut one_bytes = zero_addr.octets_vec();
+		u8::flip(&mut one_bytes, (next_len - 1) as usize);
+		let one_addr = Ipv6Addr::from(<[u8; 16]>::try_from(one_bytes.as_slice()).unwrap());
+
+		let zero_cidr = Ipv6Cidr::new(zero_addr, next_len).expect("split half is a valid network");
+		let one_cidr = Ipv6Cidr::new(one_addr, next_len).expect("split half is a valid network");
+
+		if zero_cidr.contains(&other.first_address()) {
+			result.push(one_cidr);
+			current = zero_cidr;
+		} else {
+			result.push(zero_cidr);
+			current = one_cidr;
+		}
+	}
+	result
+}
+
+impl IpCidr {
+	/// Returns the minimal list of CIDRs covering `self \ other` (i.e.
+	/// every address in `self` that isn't also in `other`).
+	///
+	/// If `self` lies entirely within `other` (including `self == other`),
+	/// returns an empty list. Otherwise, if `other` isn't contained in
+	/// `self` at all, returns `[self]` unchanged. If the families of
+	/// `self` and `other` differ, they can't overlap, so the result is
+	/// also `[self]`.
+	pub fn exclude(&self, other: &IpCidr) -> Vec<IpCidr> {
+		match (self, other) {
+			(IpCidr::V4(n), IpCidr::V4(o)) => exclude_v4(n, o).into_iter().map(IpCidr::V4).collect(),
+			(IpCidr::V6(n), IpCidr::V6(o)) => exclude_v6(n, o).into_iter().map(IpCidr::V6).collect(),
+			_ => vec![self.clone()],
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::aggregate;
+	use crate::traits::Cidr;
+	use crate::IpCidr;
+
+	fn v4(s: &str) -> IpCidr {
+		IpCidr::V4(s.parse().unwrap())
+	}
+
+	#[test]
+	fn aggregate_merges_sibling_pairs() {
+		let cidrs = vec![v4("10.0.0.0/25"), v4("10.0.0.128/25")];
+		assert_eq!(aggregate(cidrs), vec![v4("10.0.0.0/24")]);
+	}
+
+	#[test]
+	fn aggregate_merges_to_fixpoint() {
+		let cidrs = vec![
+			v4("10.0.0.0/26"),
+			v4("10.0.0.64/26"),
+			v4("10.0.0.128/26"),
+			v4("10.0.0.192/26"),
+		];
+		assert_eq!(aggregate(cidrs), vec![v4("10.0.0.0/24")]);
+	}
+
+	#[test]
+	fn aggregate_drops_subsumed_networks() {
+		let cidrs = vec![v4("10.0.0.0/24"), v4("10.0.0.0/25")];
+		assert_eq!(aggregate(cidrs), vec![v4("10.0.0.0/24")]);
+	}
+
+	#[test]
+	fn aggregate_leaves_unrelated_networks_alone() {
+		let cidrs = vec![v4("10.0.0.0/24"), v4("192.168.0.0/24")];
+		assert_eq!(aggregate(cidrs), vec![v4("10.0.0.0/24"), v4("192.168.0.0/24")]);
+	}
+
+	#[test]
+	fn exclude_splits_around_excluded_subnet() {
+		let network: IpCidr = v4("10.0.0.0/24");
+		let other: IpCidr = v4("10.0.0.128/25");
+		let mut result = network.exclude(&other);
+		result.sort_by_key(|c| c.first_address());
+		assert_eq!(result, vec![v4("10.0.0.0/25")]);
+	}
+
+	#[test]
+	fn exclude_returns_self_when_disjoint() {
+		let network: IpCidr = v4("10.0.0.0/24");
+		let other: IpCidr = v4("192.168.0.0/24");
+		assert_eq!(network.exclude(&other), vec![network.clone()]);
+	}
+
+	#[test]
+	fn exclude_returns_empty_when_self_is_subnet_of_other() {
+		let network: IpCidr = v4("10.0.0.0/24");
+		let other: IpCidr = v4("10.0.0.0/16");
+		assert_eq!(network.exclude(&other), Vec::<IpCidr>::new());
+	}
+}
@@ -1,18 +1,75 @@
+use crate::bigendian::{shared_prefix_len_widest, BigEndianManipulation};
 use crate::local_addr_parser::ParseableAddress;
 use std::str::FromStr;
 
 use super::super::errors::*;
 use super::super::traits::*;
+use super::util::Octets;
+
+// interpret `bytes` as a netmask: a contiguous run of one-bits followed
+// by zero-bits, using a leading-ones count built on BigEndianManipulation.
+// returns `None` for non-canonical masks like `255.0.255.0`.
+fn netmask_prefix_len(bytes: &[u8]) -> Option<u8> {
+	let ones = vec![0xffu8; bytes.len()];
+	let prefix_len = shared_prefix_len_widest(bytes, &ones, bytes.len() * 8);
+	if u8::is_zeroesfrom(bytes, prefix_len) {
+		Some(prefix_len as u8)
+	} else {
+		None
+	}
+}
 
 pub fn cidr_from_str<C>(s: &str) -> Result<C, NetworkParseError>
 where
 	C: Cidr,
-	C::Address: ParseableAddress,
+	C::Address: ParseableAddress + Octets,
 {
 	match s.rfind('/') {
 		None => Ok(C::new_host(C::Address::address_from_str(s)?)),
 		Some(pos) => {
-			C::new(C::Address::address_from_str(&s[0..pos])?, u8::from_str(&s[pos + 1..])?)
+			let addr = C::Address::address_from_str(&s[0..pos])?;
+			let len_part = &s[pos + 1..];
+			// a netmask written in the address family's own notation
+			// (e.g. `255.255.255.0` or `ffff:ffff::`) takes precedence
+			// over parsing the part as a plain prefix length
+			match C::Address::address_from_str(len_part) {
+				Ok(mask) if mask.octets_vec().len() == addr.octets_vec().len() => {
+					let len = netmask_prefix_len(&mask.octets_vec())
+						.ok_or(NetworkParseError::NetmaskNotContiguous)?;
+					C::new(addr, len)
+				},
+				_ => C::new(addr, u8::from_str(len_part)?),
+			}
 		},
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::traits::Cidr;
+	use crate::{Ipv4Cidr, Ipv6Cidr};
+
+	#[test]
+	fn accepts_dotted_decimal_netmask() {
+		let a = "10.0.0.0/255.255.255.0".parse::<Ipv4Cidr>().unwrap();
+		let b = "10.0.0.0/24".parse::<Ipv4Cidr>().unwrap();
+		assert_eq!(a, b);
+	}
+
+	#[test]
+	fn rejects_non_contiguous_netmask() {
+		assert!("10.0.0.0/255.0.255.0".parse::<Ipv4Cidr>().is_err());
+	}
+
+	#[test]
+	fn accepts_ipv6_netmask() {
+		let a = "2001:db8::/ffff:ffff:ffff:ffff::".parse::<Ipv6Cidr>().unwrap();
+		let b = "2001:db8::/64".parse::<Ipv6Cidr>().unwrap();
+		assert_eq!(a, b);
+	}
+
+	#[test]
+	fn still_accepts_plain_prefix_length() {
+		assert_eq!("192.168.0.0/16".parse::<Ipv4Cidr>().unwrap().network_length(), 16);
+	}
+}
@@ -0,0 +1,327 @@
+// Classification of networks against the IANA special-purpose address
+// registries (IPv4: RFC 6890 and friends, IPv6: RFC 6890 / RFC 4291 / RFC
+// 2373). A network is only reported as belonging to a range when it lies
+// *entirely* within it; a network straddling the boundary of a special
+// range is not considered part of it.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::OnceLock;
+
+use super::super::traits::*;
+use super::util::is_subnet_of;
+use super::{IpCidr, Ipv4Cidr, Ipv6Cidr};
+
+/// Scope of an IPv6 multicast address, as carried in the low nibble of
+/// the second address byte (RFC 4291 section 2.7, RFC 7346).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Ipv6MulticastScope {
+	InterfaceLocal,
+	LinkLocal,
+	RealmLocal,
+	AdminLocal,
+	SiteLocal,
+	OrganizationLocal,
+	Global,
+}
+
+// whether `network` and `other` share any address at all.
+fn overlaps<C: Cidr>(network: &C, other: &C) -> bool {
+	network.contains(&other.first_address()) || other.contains(&network.first_address())
+}
+
+// `(octets, prefix length)` pairs are built directly into `Ipv4Cidr`s /
+// `Ipv6Cidr`s, not re-parsed from a string; the `*_cache!` macros below
+// build each table at most once and cache it for the life of the
+// program instead of redoing this on every call.
+fn build_v4(ranges: &[([u8; 4], u8)]) -> Vec<Ipv4Cidr> {
+	ranges
+		.iter()
+		.map(|&(octets, len)| Ipv4Cidr::new(Ipv4Addr::from(octets), len).expect("built-in range is always valid"))
+		.collect()
+}
+
+fn build_v6(ranges: &[([u8; 16], u8)]) -> Vec<Ipv6Cidr> {
+	ranges
+		.iter()
+		.map(|&(octets, len)| Ipv6Cidr::new(Ipv6Addr::from(octets), len).expect("built-in range is always valid"))
+		.collect()
+}
+
+macro_rules! v4_cache {
+	($ranges:expr) => {{
+		static CACHE: OnceLock<Vec<Ipv4Cidr>> = OnceLock::new();
+		CACHE.get_or_init(|| build_v4($ranges)).as_slice()
+	}};
+}
+
+macro_rules! v6_cache {
+	($ranges:expr) => {{
+		static CACHE: OnceLock<Vec<Ipv6Cidr>> = OnceLock::new();
+		CACHE.get_or_init(|| build_v6($ranges)).as_slice()
+	}};
+}
+
+fn any_subnet_of<C: Cidr>(network: &C, ranges: &[C]) -> bool {
+	ranges.iter().any(|special| is_subnet_of(network, special))
+}
+
+fn any_overlaps<C: Cidr>(network: &C, ranges: &[C]) -> bool {
+	ranges.iter().any(|special| overlaps(network, special))
+}
+
+const V4_LOOPBACK: &[([u8; 4], u8)] = &[([127, 0, 0, 0], 8)];
+const V4_UNSPECIFIED: &[([u8; 4], u8)] = &[([0, 0, 0, 0], 32)];
+const V4_MULTICAST: &[([u8; 4], u8)] = &[([224, 0, 0, 0], 4)];
+const V4_LINK_LOCAL: &[([u8; 4], u8)] = &[([169, 254, 0, 0], 16)];
+const V4_DOCUMENTATION: &[([u8; 4], u8)] = &[([192, 0, 2, 0], 24), ([198, 51, 100, 0], 24), ([203, 0, 113, 0], 24)];
+const V4_BENCHMARKING: &[([u8; 4], u8)] = &[([198, 18, 0, 0], 15)];
+// everything that isn't routable on the public Internet
+const V4_RESERVED: &[([u8; 4], u8)] = &[
+	([0, 0, 0, 0], 8),
+	([10, 0, 0, 0], 8),
+	([100, 64, 0, 0], 10),
+	([127, 0, 0, 0], 8),
+	([169, 254, 0, 0], 16),
+	([172, 16, 0, 0], 12),
+	([192, 0, 0, 0], 24),
+	([192, 0, 2, 0], 24),
+	([192, 168, 0, 0], 16),
+	([198, 18, 0, 0], 15),
+	([198, 51, 100, 0], 24),
+	([203, 0, 113, 0], 24),
+	([224, 0, 0, 0], 4),
+	([240, 0, 0, 0], 4),
+	([255, 255, 255, 255], 32),
+];
+
+const V6_LOOPBACK: &[([u8; 16], u8)] = &[([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1], 128)];
+const V6_UNSPECIFIED: &[([u8; 16], u8)] = &[([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 128)];
+const V6_MULTICAST: &[([u8; 16], u8)] = &[([0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 8)];
+const V6_LINK_LOCAL: &[([u8; 16], u8)] = &[([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 10)];
+const V6_DOCUMENTATION: &[([u8; 16], u8)] = &[([0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 32)];
+const V6_BENCHMARKING: &[([u8; 16], u8)] = &[([0x20, 0x01, 0x00, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 48)];
+const V6_RESERVED: &[([u8; 16], u8)] = &[
+	([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 128),
+	([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1], 128),
+	([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0, 0, 0, 0], 96),
+	([0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 64),
+	([0x20, 0x01, 0x00, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 48),
+	([0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 32),
+	([0xfc, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 7),
+	([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 10),
+	([0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 8),
+];
+
+impl Ipv4Cidr {
+	/// Whether the network is entirely inside `127.0.0.0/8`.
+	pub fn is_loopback(&self) -> bool {
+		any_subnet_of(self, v4_cache!(V4_LOOPBACK))
+	}
+
+	/// Whether the network is the `0.0.0.0/32` singleton.
+	pub fn is_unspecified(&self) -> bool {
+		any_subnet_of(self, v4_cache!(V4_UNSPECIFIED))
+	}
+
+	/// Whether the network is entirely inside `224.0.0.0/4`.
+	pub fn is_multicast(&self) -> bool {
+		any_subnet_of(self, v4_cache!(V4_MULTICAST))
+	}
+
+	/// Whether the network is entirely inside `169.254.0.0/16`.
+	pub fn is_link_local(&self) -> bool {
+		any_subnet_of(self, v4_cache!(V4_LINK_LOCAL))
+	}
+
+	/// Whether the network is entirely inside one of the documentation
+	/// ranges (`192.0.2.0/24`, `198.51.100.0/24`, `203.0.113.0/24`).
+	pub fn is_documentation(&self) -> bool {
+		any_subnet_of(self, v4_cache!(V4_DOCUMENTATION))
+	}
+
+	/// Whether the network is entirely inside `198.18.0.0/15`.
+	pub fn is_benchmarking(&self) -> bool {
+		any_subnet_of(self, v4_cache!(V4_BENCHMARKING))
+	}
+
+	/// Whether the network is globally routable, i.e. doesn't touch any
+	/// of the reserved special-use ranges at all.
+	pub fn is_global(&self) -> bool {
+		!any_overlaps(self, v4_cache!(V4_RESERVED))
+	}
+}
+
+impl Ipv6Cidr {
+	/// Whether the network is the `::1/128` singleton.
+	pub fn is_loopback(&self) -> bool {
+		any_subnet_of(self, v6_cache!(V6_LOOPBACK))
+	}
+
+	/// Whether the network is the `::/128` singleton.
+	pub fn is_unspecified(&self) -> bool {
+		any_subnet_of(self, v6_cache!(V6_UNSPECIFIED))
+	}
+
+	/// Whether the network is entirely inside `ff00::/8`.
+	pub fn is_multicast(&self) -> bool {
+		any_subnet_of(self, v6_cache!(V6_MULTICAST))
+	}
+
+	/// Whether the network is entirely inside `fe80::/10`.
+	pub fn is_link_local(&self) -> bool {
+		any_subnet_of(self, v6_cache!(V6_LINK_LOCAL))
+	}
+
+	/// Whether the network is entirely inside `2001:db8::/32`.
+	pub fn is_documentation(&self) -> bool {
+		any_subnet_of(self, v6_cache!(V6_DOCUMENTATION))
+	}
+
+	/// Whether the network is entirely inside `2001:2::/48`.
+	pub fn is_benchmarking(&self) -> bool {
+		any_subnet_of(self, v6_cache!(V6_BENCHMARKING))
+	}
+
+	/// Whether the network is globally routable, i.e. doesn't touch any
+	/// of the reserved special-use ranges at all.
+	pub fn is_global(&self) -> bool {
+		!any_overlaps(self, v6_cache!(V6_RESERVED))
+	}
+
+	/// Multicast scope of the network, if the whole network is multicast
+	/// and long enough (`/16` or more) to pin down the scope nibble.
+	pub fn multicast_scope(&self) -> Option<Ipv6MulticastScope> {
+		if !self.is_multicast() || self.network_length() < 16 {
+			return None;
+		}
+		match self.first_address().octets()[1] & 0x0f {
+			0x1 => Some(Ipv6MulticastScope::InterfaceLocal),
+			0x2 => Some(Ipv6MulticastScope::LinkLocal),
+			0x3 => Some(Ipv6MulticastScope::RealmLocal),
+			0x4 => Some(Ipv6MulticastScope::AdminLocal),
+			0x5 => Some(Ipv6MulticastScope::SiteLocal),
+			0x8 => Some(Ipv6MulticastScope::OrganizationLocal),
+			0xe => Some(Ipv6MulticastScope::Global),
+			_ => None,
+		}
+	}
+}
+
+impl IpCidr {
+	/// Whether the network is entirely inside the loopback range for its
+	/// family (`127.0.0.0/8` or `::1/128`).
+	pub fn is_loopback(&self) -> bool {
+		match *self {
+			IpCidr::V4(ref c) => c.is_loopback(),
+			IpCidr::V6(ref c) => c.is_loopback(),
+		}
+	}
+
+	/// Whether the network is the unspecified-address singleton for its
+	/// family (`0.0.0.0/32` or `::/128`).
+	pub fn is_unspecified(&self) -> bool {
+		match *self {
+			IpCidr::V4(ref c) => c.is_unspecified(),
+			IpCidr::V6(ref c) => c.is_unspecified(),
+		}
+	}
+
+	/// Whether the network is entirely inside the multicast range for
+	/// its family (`224.0.0.0/4` or `ff00::/8`).
+	pub fn is_multicast(&self) -> bool {
+		match *self {
+			IpCidr::V4(ref c) => c.is_multicast(),
+			IpCidr::V6(ref c) => c.is_multicast(),
+		}
+	}
+
+	/// Whether the network is entirely inside the link-local range for
+	/// its family (`169.254.0.0/16` or `fe80::/10`).
+	pub fn is_link_local(&self) -> bool {
+		match *self {
+			IpCidr::V4(ref c) => c.is_link_local(),
+			IpCidr::V6(ref c) => c.is_link_local(),
+		}
+	}
+
+	/// Whether the network is entirely inside a documentation range.
+	pub fn is_documentation(&self) -> bool {
+		match *self {
+			IpCidr::V4(ref c) => c.is_documentation(),
+			IpCidr::V6(ref c) => c.is_documentation(),
+		}
+	}
+
+	/// Whether the network is entirely inside a benchmarking range
+	/// (`198.18.0.0/15` or `2001:2::/48`).
+	pub fn is_benchmarking(&self) -> bool {
+		match *self {
+			IpCidr::V4(ref c) => c.is_benchmarking(),
+			IpCidr::V6(ref c) => c.is_benchmarking(),
+		}
+	}
+
+	/// Whether the network is globally routable, i.e. doesn't touch any
+	/// of the reserved special-use ranges for its family at all.
+	pub fn is_global(&self) -> bool {
+		match *self {
+			IpCidr::V4(ref c) => c.is_global(),
+			IpCidr::V6(ref c) => c.is_global(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Ipv6MulticastScope;
+	use crate::{Ipv4Cidr, Ipv6Cidr};
+
+	#[test]
+	fn v4_special_ranges() {
+		assert!("127.0.0.0/8".parse::<Ipv4Cidr>().unwrap().is_loopback());
+		assert!("127.1.2.3/32".parse::<Ipv4Cidr>().unwrap().is_loopback());
+		assert!(!"126.0.0.0/7".parse::<Ipv4Cidr>().unwrap().is_loopback());
+		assert!(!"10.0.0.0/8".parse::<Ipv4Cidr>().unwrap().is_loopback());
+
+		assert!("0.0.0.0/32".parse::<Ipv4Cidr>().unwrap().is_unspecified());
+		assert!(!"0.0.0.0/31".parse::<Ipv4Cidr>().unwrap().is_unspecified());
+
+		assert!("224.0.0.0/4".parse::<Ipv4Cidr>().unwrap().is_multicast());
+		assert!("239.1.2.3/32".parse::<Ipv4Cidr>().unwrap().is_multicast());
+
+		assert!("169.254.1.0/24".parse::<Ipv4Cidr>().unwrap().is_link_local());
+		assert!("192.0.2.0/24".parse::<Ipv4Cidr>().unwrap().is_documentation());
+		assert!("198.18.0.0/16".parse::<Ipv4Cidr>().unwrap().is_benchmarking());
+
+		assert!(!"10.0.0.0/8".parse::<Ipv4Cidr>().unwrap().is_global());
+		assert!("8.8.8.8/32".parse::<Ipv4Cidr>().unwrap().is_global());
+		// covers 8.0.0.0-11.255.255.255, so it straddles (and touches) the
+		// reserved 10.0.0.0/8 range
+		assert!(!"8.0.0.0/6".parse::<Ipv4Cidr>().unwrap().is_global());
+	}
+
+	#[test]
+	fn v6_special_ranges() {
+		assert!("::1/128".parse::<Ipv6Cidr>().unwrap().is_loopback());
+		assert!(!"::/127".parse::<Ipv6Cidr>().unwrap().is_loopback());
+
+		assert!("::/128".parse::<Ipv6Cidr>().unwrap().is_unspecified());
+		assert!("ff00::/8".parse::<Ipv6Cidr>().unwrap().is_multicast());
+		assert!("fe80::/10".parse::<Ipv6Cidr>().unwrap().is_link_local());
+		assert!("2001:db8::/32".parse::<Ipv6Cidr>().unwrap().is_documentation());
+		assert!("2001:2::/48".parse::<Ipv6Cidr>().unwrap().is_benchmarking());
+
+		assert!(!"fe80::/10".parse::<Ipv6Cidr>().unwrap().is_global());
+		assert!("2606:4700::/32".parse::<Ipv6Cidr>().unwrap().is_global());
+	}
+
+	#[test]
+	fn v6_multicast_scope() {
+		assert_eq!(Some(Ipv6MulticastScope::LinkLocal), "ff02::/16".parse::<Ipv6Cidr>().unwrap().multicast_scope());
+		assert_eq!(Some(Ipv6MulticastScope::Global), "ff0e::/16".parse::<Ipv6Cidr>().unwrap().multicast_scope());
+		// not long enough to pin down the scope nibble
+		assert_eq!(None, "ff00::/8".parse::<Ipv6Cidr>().unwrap().multicast_scope());
+		// not multicast at all
+		assert_eq!(None, "2001:db8::/32".parse::<Ipv6Cidr>().unwrap().multicast_scope());
+	}
+}
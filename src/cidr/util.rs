@@ -0,0 +1,38 @@
+// shared helpers used across the `cidr` submodules; kept here instead of
+// being reinvented per file.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use super::super::traits::*;
+
+/// whether `network` lies entirely within `other`.
+pub(crate) fn is_subnet_of<C: Cidr>(network: &C, other: &C) -> bool {
+	other.network_length() <= network.network_length() && other.contains(&network.first_address())
+}
+
+/// byte representation of an address, used wherever a generic `Cidr::Address`
+/// needs to be fed into the slice-based `BigEndianManipulation` ops.
+pub(crate) trait Octets {
+	fn octets_vec(&self) -> Vec<u8>;
+}
+
+impl Octets for IpAddr {
+	fn octets_vec(&self) -> Vec<u8> {
+		match *self {
+			IpAddr::V4(ref a) => a.octets().to_vec(),
+			IpAddr::V6(ref a) => a.octets().to_vec(),
+		}
+	}
+}
+
+impl Octets for Ipv4Addr {
+	fn octets_vec(&self) -> Vec<u8> {
+		self.octets().to_vec()
+	}
+}
+
+impl Octets for Ipv6Addr {
+	fn octets_vec(&self) -> Vec<u8> {
+		self.octets().to_vec()
+	}
+}
@@ -0,0 +1,49 @@
+use std::error::Error;
+use std::fmt;
+use std::net::AddrParseError;
+use std::num::ParseIntError;
+
+/// Error parsing a network (CIDR) from its string representation, e.g.
+/// via `"192.168.0.0/24".parse::<IpCidr>()`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum NetworkParseError {
+	/// the address part failed to parse
+	AddrParseError(AddrParseError),
+	/// the part after the `/` is neither a valid prefix length nor a
+	/// well-formed netmask
+	NetworkLengthParseError,
+	/// the prefix length (or equivalent netmask) is longer than the
+	/// address family allows
+	NetworkLengthTooLongError,
+	/// the part after the `/` parsed as an address of the same family,
+	/// but isn't a contiguous run of one-bits followed by zero-bits
+	/// (e.g. `255.0.255.0`)
+	NetmaskNotContiguous,
+}
+
+impl fmt::Display for NetworkParseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			NetworkParseError::AddrParseError(ref e) => write!(f, "couldn't parse address in network: {}", e),
+			NetworkParseError::NetworkLengthParseError => write!(f, "couldn't parse length in network"),
+			NetworkParseError::NetworkLengthTooLongError => write!(f, "network length too long for family"),
+			NetworkParseError::NetmaskNotContiguous => {
+				write!(f, "netmask is not a contiguous run of one-bits followed by zero-bits")
+			},
+		}
+	}
+}
+
+impl Error for NetworkParseError {}
+
+impl From<AddrParseError> for NetworkParseError {
+	fn from(e: AddrParseError) -> Self {
+		NetworkParseError::AddrParseError(e)
+	}
+}
+
+impl From<ParseIntError> for NetworkParseError {
+	fn from(_: ParseIntError) -> Self {
+		NetworkParseError::NetworkLengthParseError
+	}
+}